@@ -1,12 +1,17 @@
+use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use derive_more::{AsRef, Deref};
-use derive_new::new;
 
 use eyre::{Context, Result};
 use hyperlane_base::{MultisigCheckpointSyncer, ValidatorWithWeight, Weight};
-use hyperlane_core::{unwrap_or_none_result, HyperlaneMessage, H256};
+use hyperlane_core::{
+    accumulator::merkle::Proof as MerkleProof, unwrap_or_none_result, MultisigSignedCheckpoint,
+    HyperlaneMessage, H256,
+};
 use tracing::debug;
 
 use crate::msg::metadata::MessageMetadataBuilder;
@@ -15,8 +20,290 @@ use super::base::{
     fetch_unit_validator_requirements, MetadataToken, MultisigIsmMetadataBuilder, MultisigMetadata,
 };
 
-#[derive(Debug, Clone, Deref, new, AsRef)]
-pub struct MerkleRootMultisigMetadataBuilder(MessageMetadataBuilder);
+#[derive(Debug, Clone, Deref, AsRef)]
+pub struct MerkleRootMultisigMetadataBuilder {
+    #[deref]
+    #[as_ref]
+    base: MessageMetadataBuilder,
+    cache: Arc<dyn MetadataCache>,
+}
+
+impl MerkleRootMultisigMetadataBuilder {
+    /// Wraps a [`MessageMetadataBuilder`], owning an in-memory metadata cache by default so
+    /// retry loops on this builder reuse prior work. The in-memory cache does not survive a
+    /// relayer restart. Use [`Self::with_cache`] to supply a persistent backend.
+    pub fn new(base: MessageMetadataBuilder) -> Self {
+        Self {
+            base,
+            cache: Arc::new(InMemoryMetadataCache::default()),
+        }
+    }
+
+    /// Wraps a [`MessageMetadataBuilder`] with an explicit [`MetadataCache`] backend.
+    pub fn with_cache(base: MessageMetadataBuilder, cache: Arc<dyn MetadataCache>) -> Self {
+        Self { base, cache }
+    }
+
+    /// Returns the cache consulted before hitting the `MultisigCheckpointSyncer`.
+    pub fn metadata_cache(&self) -> &dyn MetadataCache {
+        self.cache.as_ref()
+    }
+}
+
+/// Persistence layer sitting behind [`MerkleRootMultisigMetadataBuilder`], following the same
+/// get/set store pattern used elsewhere for relayer state. The trait is builder-agnostic so the
+/// sibling multisig builders can adopt it later; only the merkle-root builder is wired for now.
+///
+/// Assembled [`MultisigMetadata`] and the intermediate quorum checkpoints that produced it are
+/// cached so that retry loops and relayer restarts reuse prior work instead of re-querying
+/// validator storage. Quorum checkpoints are keyed by `(origin_domain, leaf_index,
+/// validator_set)` so a validator-set or threshold change misses the cache rather than serving a
+/// checkpoint signed by the old set; assembled metadata is keyed by `(origin_domain, leaf_index,
+/// checkpoint_root)`, which is content-addressed by the signed root. The only backend is the
+/// in-memory [`InMemoryMetadataCache`], whose contents are lost when the relayer restarts.
+pub trait MetadataCache: Send + Sync + Debug {
+    /// Returns a cached quorum checkpoint for `(origin_domain, leaf_index, validator_set)`, if
+    /// any. `validator_set` fingerprints the validators and threshold so a set change misses.
+    fn get_checkpoint(
+        &self,
+        origin_domain: u32,
+        leaf_index: u32,
+        validator_set: H256,
+    ) -> Option<MultisigSignedCheckpoint>;
+
+    /// Stores a quorum checkpoint for `(origin_domain, leaf_index, validator_set)`.
+    fn set_checkpoint(
+        &self,
+        origin_domain: u32,
+        leaf_index: u32,
+        validator_set: H256,
+        checkpoint: MultisigSignedCheckpoint,
+    );
+
+    /// Returns cached metadata for `(origin_domain, leaf_index, checkpoint_root)`, if any.
+    fn get_metadata(
+        &self,
+        origin_domain: u32,
+        leaf_index: u32,
+        checkpoint_root: H256,
+    ) -> Option<MultisigMetadata>;
+
+    /// Stores assembled metadata for `(origin_domain, leaf_index, checkpoint_root)`.
+    fn set_metadata(
+        &self,
+        origin_domain: u32,
+        leaf_index: u32,
+        checkpoint_root: H256,
+        metadata: MultisigMetadata,
+    );
+}
+
+/// In-memory [`MetadataCache`] backed by a pair of mutex-guarded maps. This is the default
+/// backend and is sufficient for a single long-running relayer process.
+#[derive(Debug, Default)]
+pub struct InMemoryMetadataCache {
+    checkpoints: Mutex<HashMap<(u32, u32, H256), MultisigSignedCheckpoint>>,
+    metadata: Mutex<HashMap<(u32, u32, H256), MultisigMetadata>>,
+}
+
+impl MetadataCache for InMemoryMetadataCache {
+    fn get_checkpoint(
+        &self,
+        origin_domain: u32,
+        leaf_index: u32,
+        validator_set: H256,
+    ) -> Option<MultisigSignedCheckpoint> {
+        self.checkpoints
+            .lock()
+            .expect("metadata cache mutex poisoned")
+            .get(&(origin_domain, leaf_index, validator_set))
+            .cloned()
+    }
+
+    fn set_checkpoint(
+        &self,
+        origin_domain: u32,
+        leaf_index: u32,
+        validator_set: H256,
+        checkpoint: MultisigSignedCheckpoint,
+    ) {
+        self.checkpoints
+            .lock()
+            .expect("metadata cache mutex poisoned")
+            .insert((origin_domain, leaf_index, validator_set), checkpoint);
+    }
+
+    fn get_metadata(
+        &self,
+        origin_domain: u32,
+        leaf_index: u32,
+        checkpoint_root: H256,
+    ) -> Option<MultisigMetadata> {
+        self.metadata
+            .lock()
+            .expect("metadata cache mutex poisoned")
+            .get(&(origin_domain, leaf_index, checkpoint_root))
+            .cloned()
+    }
+
+    fn set_metadata(
+        &self,
+        origin_domain: u32,
+        leaf_index: u32,
+        checkpoint_root: H256,
+        metadata: MultisigMetadata,
+    ) {
+        self.metadata
+            .lock()
+            .expect("metadata cache mutex poisoned")
+            .insert((origin_domain, leaf_index, checkpoint_root), metadata);
+    }
+}
+
+/// Fingerprints the validator set and threshold into a single `H256`, used as a cache-key
+/// component so a cached quorum checkpoint is only reused while the signer set that produced it
+/// is unchanged; a membership, weight, or threshold change yields a different fingerprint and
+/// therefore a cache miss rather than a stale checkpoint.
+fn validator_set_fingerprint(validators: &[ValidatorWithWeight], threshold_weight: Weight) -> H256 {
+    // Hash each validator together with its weight, in order, so that even two validators
+    // swapping weights changes the fingerprint.
+    let mut hasher = DefaultHasher::new();
+    for validator in validators {
+        validator.validator.hash(&mut hasher);
+        validator.weight.hash(&mut hasher);
+    }
+    threshold_weight.hash(&mut hasher);
+    H256::from_low_u64_be(hasher.finish())
+}
+
+/// Greedily selects a minimal-cardinality subset of available validator signatures whose
+/// summed weight reaches `threshold_weight`, minimizing the signature count (and thus
+/// calldata and on-chain verification gas) when per-signature cost is uniform.
+///
+/// `available` pairs each available signer's validator index with its weight. Signers are
+/// considered in descending weight order, ties broken by ascending validator index for
+/// determinism; the returned indices are sorted ascending, as required by the on-chain ISM.
+/// Returns `None` when the available weight never reaches the threshold.
+fn select_minimal_signature_subset(
+    available: &[(usize, Weight)],
+    threshold_weight: Weight,
+) -> Option<Vec<usize>> {
+    let mut ordered = available.to_vec();
+    ordered.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let mut selected = Vec::new();
+    let mut accumulated: Weight = 0;
+    for (index, weight) in ordered {
+        if accumulated >= threshold_weight {
+            break;
+        }
+        selected.push(index);
+        accumulated += weight;
+    }
+    if accumulated < threshold_weight {
+        return None;
+    }
+    selected.sort_unstable();
+    Some(selected)
+}
+
+/// Trims `quorum_checkpoint` down to a minimal-cardinality subset of signatures (see
+/// [`select_minimal_signature_subset`]) so the `Signatures` token doesn't carry more
+/// signatures than the ISM threshold requires. Surviving signatures are kept in ascending
+/// validator-index order. If any signer can't be mapped to a validator, or the available
+/// weight is already insufficient, the checkpoint is left untouched.
+fn minimize_checkpoint_signatures(
+    quorum_checkpoint: &mut MultisigSignedCheckpoint,
+    validators: &[ValidatorWithWeight],
+    threshold_weight: Weight,
+) {
+    let signing_hash = quorum_checkpoint.checkpoint.signing_hash();
+    let mut available = Vec::with_capacity(quorum_checkpoint.signatures.len());
+    for signature in &quorum_checkpoint.signatures {
+        let signer = match signature.recover(signing_hash) {
+            Ok(signer) => H256::from(signer),
+            Err(_) => return,
+        };
+        match validators.iter().position(|v| v.validator == signer) {
+            Some(index) => available.push((index, validators[index].weight)),
+            None => return,
+        }
+    }
+    if let Some(selected) = select_minimal_signature_subset(&available, threshold_weight) {
+        let selected: HashSet<usize> = selected.into_iter().collect();
+        let mut kept: Vec<_> = quorum_checkpoint
+            .signatures
+            .drain(..)
+            .zip(available.iter().map(|(index, _)| *index))
+            .filter(|(_, index)| selected.contains(index))
+            .collect();
+        kept.sort_by_key(|(_, index)| *index);
+        quorum_checkpoint.signatures = kept.into_iter().map(|(sig, _)| sig).collect();
+    }
+}
+
+/// Recomputes the merkle root implied by `proof` and checks it, and the proof index, against
+/// the signed checkpoint root and claimed `leaf_index`. This is the stateless, crypto-free
+/// half of [`verify_assembled_metadata`].
+fn proof_matches_checkpoint(proof: &MerkleProof, signed_root: H256, leaf_index: u32) -> bool {
+    let recomputed_root = proof.root();
+    if recomputed_root != signed_root {
+        debug!(
+            ?recomputed_root,
+            ?signed_root,
+            "Recomputed merkle root does not match signed checkpoint root"
+        );
+        return false;
+    }
+    if proof.index as u32 != leaf_index {
+        debug!(
+            proof_index = proof.index,
+            leaf_index, "Proof index does not match message merkle leaf index"
+        );
+        return false;
+    }
+    true
+}
+
+/// Locally recomputes and validates assembled multisig metadata before it is submitted
+/// on-chain, so a bad proof or under-weight signer set surfaces as a catchable relayer
+/// error rather than a silent revert.
+///
+/// Returns `true` only when the proof folds up to the signed checkpoint root, the proof
+/// index matches the claimed `leaf_index`, and the weight of the signers recovered from the
+/// checkpoint signatures clears `threshold_weight`.
+fn verify_assembled_metadata(
+    leaf_index: u32,
+    proof: &MerkleProof,
+    quorum_checkpoint: &MultisigSignedCheckpoint,
+    validators: &[ValidatorWithWeight],
+    threshold_weight: Weight,
+) -> bool {
+    if !proof_matches_checkpoint(proof, quorum_checkpoint.checkpoint.checkpoint.root, leaf_index) {
+        return false;
+    }
+    let signing_hash = quorum_checkpoint.checkpoint.signing_hash();
+    let mut recovered_weight: Weight = 0;
+    for signature in &quorum_checkpoint.signatures {
+        let signer = match signature.recover(signing_hash) {
+            Ok(signer) => H256::from(signer),
+            Err(err) => {
+                debug!(?err, "Couldn't recover signer from checkpoint signature");
+                return false;
+            }
+        };
+        if let Some(validator) = validators.iter().find(|v| v.validator == signer) {
+            recovered_weight += validator.weight;
+        }
+    }
+    if recovered_weight < threshold_weight {
+        debug!(
+            recovered_weight,
+            threshold_weight, "Recovered signer weight is below the ISM threshold"
+        );
+        return false;
+    }
+    true
+}
 #[async_trait]
 impl MultisigIsmMetadataBuilder for MerkleRootMultisigMetadataBuilder {
     fn token_layout(&self) -> Vec<MetadataToken> {
@@ -51,27 +338,150 @@ impl MultisigIsmMetadataBuilder for MerkleRootMultisigMetadataBuilder {
                 "No merkle leaf found for message id, must have not been enqueued in the tree"
             )
         );
-        let quorum_checkpoint = unwrap_or_none_result!(
+        let cache = self.metadata_cache();
+        let origin_domain = self.origin_domain();
+        // Keying the checkpoint cache on the validator set means a set/threshold change misses
+        // the cache instead of serving a checkpoint signed by the superseded set.
+        let validator_set = validator_set_fingerprint(validators, threshold_weight);
+        // Reuse a previously fetched quorum checkpoint for this leaf before contacting the
+        // syncer, so retry loops (and, with a persistent backend, relayer restarts) don't
+        // re-query validator storage.
+        let quorum_checkpoint = match cache.get_checkpoint(origin_domain, leaf_index, validator_set)
+        {
+            Some(quorum_checkpoint) => quorum_checkpoint,
+            None => {
+                let quorum_checkpoint = unwrap_or_none_result!(
+                    checkpoint_syncer
+                        .fetch_checkpoint_in_range(
+                            validators,
+                            threshold_weight,
+                            leaf_index,
+                            highest_leaf_index,
+                            origin_domain,
+                            self.destination_domain(),
+                        )
+                        .await
+                        .context(CTX)?,
+                    debug!(
+                        leaf_index,
+                        highest_leaf_index, "Couldn't get checkpoint in range"
+                    )
+                );
+                cache.set_checkpoint(
+                    origin_domain,
+                    leaf_index,
+                    validator_set,
+                    quorum_checkpoint.clone(),
+                );
+                quorum_checkpoint
+            }
+        };
+        let checkpoint_root = quorum_checkpoint.checkpoint.checkpoint.root;
+        if let Some(metadata) = cache.get_metadata(origin_domain, leaf_index, checkpoint_root) {
+            return Ok(Some(metadata));
+        }
+        // Drop redundant signatures so the on-chain ISM verifies the minimal set for quorum.
+        let mut quorum_checkpoint = quorum_checkpoint;
+        minimize_checkpoint_signatures(&mut quorum_checkpoint, validators, threshold_weight);
+        let proof = self
+            .get_proof(leaf_index, quorum_checkpoint.checkpoint.checkpoint)
+            .await
+            .context(CTX)?;
+        if !verify_assembled_metadata(
+            leaf_index,
+            &proof,
+            &quorum_checkpoint,
+            validators,
+            threshold_weight,
+        ) {
+            debug!(
+                leaf_index,
+                "Assembled metadata failed local verification, refusing to submit"
+            );
+            return Ok(None);
+        }
+        let metadata = MultisigMetadata::new(quorum_checkpoint, leaf_index, Some(proof));
+        cache.set_metadata(origin_domain, leaf_index, checkpoint_root, metadata.clone());
+        Ok(Some(metadata))
+    }
+
+    // fetches the validators and threshold for the unit variant - each validator has a weight of 1
+    async fn ism_validator_requirements(
+        &self,
+        ism_address: H256,
+        message: &HyperlaneMessage,
+    ) -> Result<(Vec<ValidatorWithWeight>, Weight)> {
+        fetch_unit_validator_requirements(self, ism_address, message).await
+    }
+}
+
+impl MerkleRootMultisigMetadataBuilder {
+    /// Builds metadata proving `message` against a caller-specified historical checkpoint,
+    /// rather than auto-selecting the newest quorum checkpoint via `fetch_checkpoint_in_range`.
+    ///
+    /// This mirrors querying a merkle tree at a specific version: the `MerkleProof` is computed
+    /// relative to the checkpoint at `checkpoint_index`, which is needed for reorg-resilient
+    /// relaying and for re-submitting against a checkpoint a destination ISM has already
+    /// accepted, avoiding churn as new leaves keep advancing the highest known leaf index.
+    pub async fn fetch_metadata_at_checkpoint(
+        &self,
+        validators: &[ValidatorWithWeight],
+        threshold_weight: Weight,
+        message: &HyperlaneMessage,
+        checkpoint_index: u32,
+        checkpoint_syncer: &MultisigCheckpointSyncer,
+    ) -> Result<Option<MultisigMetadata>> {
+        const CTX: &str = "When fetching MerkleRootMultisig metadata at checkpoint";
+        let leaf_index = unwrap_or_none_result!(
+            self.get_merkle_leaf_id_by_message_id(message.id())
+                .await
+                .context(CTX)?,
+            debug!(
+                ?message,
+                "No merkle leaf found for message id, must have not been enqueued in the tree"
+            )
+        );
+        if checkpoint_index < leaf_index {
+            debug!(
+                checkpoint_index,
+                leaf_index, "Requested checkpoint precedes the message's merkle leaf"
+            );
+            return Ok(None);
+        }
+        // Pin the quorum checkpoint to exactly the requested index instead of the newest one.
+        let mut quorum_checkpoint = unwrap_or_none_result!(
             checkpoint_syncer
                 .fetch_checkpoint_in_range(
                     validators,
                     threshold_weight,
-                    leaf_index,
-                    highest_leaf_index,
+                    checkpoint_index,
+                    checkpoint_index,
                     self.origin_domain(),
                     self.destination_domain(),
                 )
                 .await
                 .context(CTX)?,
-            debug!(
-                leaf_index,
-                highest_leaf_index, "Couldn't get checkpoint in range"
-            )
+            debug!(checkpoint_index, "Couldn't get quorum checkpoint at index")
         );
+        minimize_checkpoint_signatures(&mut quorum_checkpoint, validators, threshold_weight);
         let proof = self
             .get_proof(leaf_index, quorum_checkpoint.checkpoint.checkpoint)
             .await
             .context(CTX)?;
+        if !verify_assembled_metadata(
+            leaf_index,
+            &proof,
+            &quorum_checkpoint,
+            validators,
+            threshold_weight,
+        ) {
+            debug!(
+                leaf_index,
+                checkpoint_index,
+                "Assembled metadata failed local verification, refusing to submit"
+            );
+            return Ok(None);
+        }
         Ok(Some(MultisigMetadata::new(
             quorum_checkpoint,
             leaf_index,
@@ -79,12 +489,173 @@ impl MultisigIsmMetadataBuilder for MerkleRootMultisigMetadataBuilder {
         )))
     }
 
-    // fetches the validators and threshold for the unit variant - each validator has a weight of 1
-    async fn ism_validator_requirements(
+    /// Fetches metadata for a batch of messages sharing this builder's origin and ISM,
+    /// amortizing checkpoint retrieval across the whole batch.
+    ///
+    /// A single quorum checkpoint covering the highest leaf index in `messages` is fetched
+    /// once from the `MultisigCheckpointSyncer`; every message's `MerkleProof` is then derived
+    /// against that shared checkpoint root. The returned vector is aligned with `messages`,
+    /// with `None` for any message that has not been enqueued in the merkle tree or whose
+    /// assembled metadata fails local verification. Every entry is `None` when the highest
+    /// known leaf index or a covering quorum checkpoint can't be obtained.
+    ///
+    /// Wiring this into the message processor's catch-up loop — batching the pending messages
+    /// that share an origin/ISM before calling out to the syncer — is intentionally out of
+    /// scope for this change: the submit loop lives in the processor, not in this module, and
+    /// driving it from here would couple metadata assembly to operation scheduling. This method
+    /// is the building block that call site will use.
+    pub async fn fetch_metadata_batch(
         &self,
-        ism_address: H256,
-        message: &HyperlaneMessage,
-    ) -> Result<(Vec<ValidatorWithWeight>, Weight)> {
-        fetch_unit_validator_requirements(self, ism_address, message).await
+        validators: &[ValidatorWithWeight],
+        threshold_weight: Weight,
+        messages: &[HyperlaneMessage],
+        checkpoint_syncer: &MultisigCheckpointSyncer,
+    ) -> Result<Vec<Option<MultisigMetadata>>> {
+        const CTX: &str = "When fetching MerkleRootMultisig metadata batch";
+        if messages.is_empty() {
+            return Ok(vec![]);
+        }
+        let highest_leaf_index = match self.highest_known_leaf_index().await {
+            Some(index) => index,
+            None => {
+                debug!("Couldn't get highest known leaf index");
+                return Ok(vec![None; messages.len()]);
+            }
+        };
+        // Resolve each message's leaf index up front so the batch can pick the single
+        // checkpoint index that covers all of them.
+        let mut leaf_indices = Vec::with_capacity(messages.len());
+        for message in messages {
+            let leaf_index = self
+                .get_merkle_leaf_id_by_message_id(message.id())
+                .await
+                .context(CTX)?;
+            if leaf_index.is_none() {
+                debug!(
+                    ?message,
+                    "No merkle leaf found for message id, must have not been enqueued in the tree"
+                );
+            }
+            leaf_indices.push(leaf_index);
+        }
+        let max_leaf_index = match leaf_indices.iter().flatten().max().copied() {
+            Some(max) => max,
+            None => return Ok(vec![None; messages.len()]),
+        };
+        let quorum_checkpoint = match checkpoint_syncer
+            .fetch_checkpoint_in_range(
+                validators,
+                threshold_weight,
+                max_leaf_index,
+                highest_leaf_index,
+                self.origin_domain(),
+                self.destination_domain(),
+            )
+            .await
+            .context(CTX)?
+        {
+            Some(quorum_checkpoint) => quorum_checkpoint,
+            None => {
+                debug!(
+                    max_leaf_index,
+                    highest_leaf_index, "Couldn't get checkpoint in range"
+                );
+                return Ok(vec![None; messages.len()]);
+            }
+        };
+        // Trim the shared checkpoint once to the minimal signer set, then verify every
+        // message's assembled metadata so the batch path upholds the same no-revert guarantee
+        // as the single-message path.
+        let mut quorum_checkpoint = quorum_checkpoint;
+        minimize_checkpoint_signatures(&mut quorum_checkpoint, validators, threshold_weight);
+        let mut metadata = Vec::with_capacity(messages.len());
+        for leaf_index in leaf_indices {
+            match leaf_index {
+                Some(leaf_index) => {
+                    let proof = self
+                        .get_proof(leaf_index, quorum_checkpoint.checkpoint.checkpoint)
+                        .await
+                        .context(CTX)?;
+                    if !verify_assembled_metadata(
+                        leaf_index,
+                        &proof,
+                        &quorum_checkpoint,
+                        validators,
+                        threshold_weight,
+                    ) {
+                        debug!(
+                            leaf_index,
+                            "Assembled metadata failed local verification, refusing to submit"
+                        );
+                        metadata.push(None);
+                        continue;
+                    }
+                    metadata.push(Some(MultisigMetadata::new(
+                        quorum_checkpoint.clone(),
+                        leaf_index,
+                        Some(proof),
+                    )));
+                }
+                None => metadata.push(None),
+            }
+        }
+        Ok(metadata)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn selects_minimal_subset_by_descending_weight() {
+        // Weights 5,3,2,1 at indices 0..4; threshold 7 is reached by 5 + 3.
+        let available = vec![(0, 5), (1, 3), (2, 2), (3, 1)];
+        assert_eq!(select_minimal_signature_subset(&available, 7), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn stops_as_soon_as_threshold_is_reached() {
+        let available = vec![(0, 5), (1, 5)];
+        assert_eq!(select_minimal_signature_subset(&available, 5), Some(vec![0]));
+    }
+
+    #[test]
+    fn breaks_weight_ties_by_ascending_index() {
+        // Equal weights: the two lowest indices win, returned in ascending order.
+        let available = vec![(2, 1), (0, 1), (1, 1)];
+        assert_eq!(select_minimal_signature_subset(&available, 2), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn returns_none_when_threshold_unreachable() {
+        let available = vec![(0, 1), (1, 1)];
+        assert_eq!(select_minimal_signature_subset(&available, 5), None);
+    }
+
+    #[test]
+    fn selected_indices_are_sorted_ascending() {
+        // The high-weight signer at index 5 is picked first, then index 0 on the weight tie;
+        // the selection is returned in ascending index order.
+        let available = vec![(0, 1), (5, 9), (3, 1)];
+        assert_eq!(select_minimal_signature_subset(&available, 10), Some(vec![0, 5]));
+    }
+
+    #[test]
+    fn proof_matches_only_its_own_root_and_index() {
+        let mut proof = MerkleProof {
+            leaf: H256::zero(),
+            index: 3,
+            path: [H256::zero(); 32],
+        };
+        let root = proof.root();
+        assert!(proof_matches_checkpoint(&proof, root, 3));
+        // A different expected root is rejected.
+        assert!(!proof_matches_checkpoint(&proof, H256::repeat_byte(0xff), 3));
+        // A mismatched leaf index is rejected.
+        assert!(!proof_matches_checkpoint(&proof, root, 4));
+        // Changing the leaf changes the recomputed root, so the old root no longer matches.
+        proof.leaf = H256::repeat_byte(0x01);
+        assert!(!proof_matches_checkpoint(&proof, root, 3));
     }
 }